@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+use std::fs;
+
+use crate::BUILTINS;
+
+/// Returns the sorted list of candidates for the word currently being
+/// completed in `line`, along with the prefix that was matched against.
+pub fn complete(line: &str, executables: &HashMap<String, String>) -> (Vec<String>, String) {
+    let word_start = line.rfind(' ').map(|i| i + 1).unwrap_or(0);
+    let prefix = line[word_start..].to_string();
+    let is_first_word = line[..word_start].trim().is_empty();
+
+    let candidates = if is_first_word {
+        complete_command(&prefix, executables)
+    } else {
+        complete_path(&prefix)
+    };
+
+    (candidates, prefix)
+}
+
+fn complete_command(prefix: &str, executables: &HashMap<String, String>) -> Vec<String> {
+    let mut candidates: Vec<String> = BUILTINS
+        .iter()
+        .map(|b| b.to_string())
+        .chain(executables.keys().cloned())
+        .filter(|name| name.starts_with(prefix))
+        .collect();
+    candidates.sort();
+    candidates.dedup();
+    candidates
+}
+
+fn complete_path(prefix: &str) -> Vec<String> {
+    let (dir, file_prefix) = match prefix.rfind('/') {
+        Some(i) => (prefix[..=i].to_string(), prefix[i + 1..].to_string()),
+        None => (String::from("."), prefix.to_string()),
+    };
+    // Read the `~`-expanded directory, but show candidates under the
+    // directory as typed so each one starts with `prefix`.
+    let search_dir = shellexpand::tilde(&dir).to_string();
+
+    let mut candidates = Vec::new();
+    if let Ok(entries) = fs::read_dir(&search_dir) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !name.starts_with(&file_prefix) {
+                continue;
+            }
+            let is_dir = entry.path().is_dir();
+            let shown = if dir == "." {
+                name
+            } else {
+                format!("{}{}", dir, name)
+            };
+            candidates.push(if is_dir { format!("{}/", shown) } else { shown });
+        }
+    }
+    candidates.sort();
+    candidates
+}
+
+/// Longest string that every candidate in `candidates` starts with.
+pub fn common_prefix(candidates: &[String]) -> String {
+    let mut prefix = match candidates.first() {
+        Some(first) => first.clone(),
+        None => return String::new(),
+    };
+    for candidate in &candidates[1..] {
+        while !candidate.starts_with(&prefix) {
+            prefix.pop();
+        }
+    }
+    prefix
+}
@@ -1,10 +1,130 @@
-use std::collections::{HashMap, VecDeque};
+mod completer;
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::fs::{self, OpenOptions};
-use std::io::{self, Write};
+use std::io::{self, BufRead, Read, Write};
 use std::process::{Child, Command, Stdio};
 use std::{env, path};
 
-static BUILTINS: [&str; 6] = ["echo", "exit", "type", "pwd", "cd", "history"];
+use termios::{tcsetattr, Termios, ECHO, ICANON, TCSANOW, VMIN, VTIME};
+
+pub static BUILTINS: [&str; 10] = [
+    "echo", "exit", "type", "pwd", "cd", "history", "export", "unset", "alias", "unalias",
+];
+
+/// Shell-local variables, seeded from the process environment, that back
+/// `$NAME` / `${NAME}` expansion and the `export`/`unset` builtins.
+struct Config {
+    vars: HashMap<String, String>,
+    aliases: BTreeMap<String, String>,
+    status: i32,
+}
+
+impl Config {
+    fn new() -> Self {
+        Config {
+            vars: env::vars().collect(),
+            aliases: BTreeMap::new(),
+            status: 0,
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<String> {
+        self.vars
+            .get(name)
+            .cloned()
+            .or_else(|| env::var(name).ok())
+    }
+
+    fn set(&mut self, name: &str, value: &str) {
+        self.vars.insert(name.to_string(), value.to_string());
+    }
+
+    fn unset(&mut self, name: &str) {
+        self.vars.remove(name);
+    }
+
+    fn status(&self) -> i32 {
+        self.status
+    }
+
+    fn set_status(&mut self, status: i32) {
+        self.status = status;
+    }
+}
+
+/// Parses a `NAME=value` word into its parts, rejecting anything whose
+/// name isn't a valid identifier.
+fn parse_assignment(word: &str) -> Option<(String, String)> {
+    let eq_pos = word.find('=')?;
+    let (name, value) = (&word[..eq_pos], &word[eq_pos + 1..]);
+    let mut chars = name.chars();
+    let first = chars.next()?;
+    if !(first.is_alphabetic() || first == '_') {
+        return None;
+    }
+    if !chars.clone().all(|c| c.is_alphanumeric() || c == '_') {
+        return None;
+    }
+    Some((name.to_string(), value.to_string()))
+}
+
+/// Resolves a variable name to its value, special-casing `status` (an
+/// alias for the last pipeline's exit code, alongside `$?`).
+fn lookup_variable(name: &str, config: &Config) -> String {
+    if name == "status" {
+        config.status().to_string()
+    } else {
+        config.get(name).unwrap_or_default()
+    }
+}
+
+/// Expands `$NAME` and `${NAME}` references in `input` against `config`,
+/// falling back to the real environment via `Config::get`.
+fn expand_variables(input: &str, config: &Config) -> String {
+    let mut result = String::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            result.push(c);
+            continue;
+        }
+
+        if chars.peek() == Some(&'?') {
+            chars.next();
+            result.push_str(&config.status().to_string());
+        } else if chars.peek() == Some(&'{') {
+            chars.next();
+            let mut name = String::new();
+            for c in chars.by_ref() {
+                if c == '}' {
+                    break;
+                }
+                name.push(c);
+            }
+            result.push_str(&lookup_variable(&name, config));
+        } else if chars
+            .peek()
+            .is_some_and(|c| c.is_alphanumeric() || *c == '_')
+        {
+            let mut name = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_alphanumeric() || c == '_' {
+                    name.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            result.push_str(&lookup_variable(&name, config));
+        } else {
+            result.push('$');
+        }
+    }
+
+    result
+}
 
 fn is_executable(path: &path::Path) -> bool {
     path.is_file()
@@ -38,68 +158,243 @@ fn load_executables() -> HashMap<String, String> {
     executables
 }
 
-fn run_builtin(cmd: String, args: Vec<String>) -> Option<String> {
+fn history_file_path() -> String {
+    env::var("HISTFILE").unwrap_or_else(|_| shellexpand::tilde("~/.shell_history").to_string())
+}
+
+fn load_history(path: &str) -> VecDeque<String> {
+    fs::read_to_string(path)
+        .map(|contents| contents.lines().map(|line| line.to_string()).collect())
+        .unwrap_or_default()
+}
+
+fn save_history(path: &str, history: &VecDeque<String>) {
+    let contents = history.iter().cloned().collect::<Vec<_>>().join("\n");
+    let _ = fs::write(path, contents);
+}
+
+fn run_builtin(
+    cmd: String,
+    args: Vec<String>,
+    history: &mut VecDeque<String>,
+    config: &mut Config,
+) -> (Option<String>, i32) {
     let executables = load_executables();
     match cmd.as_str() {
-        "echo" => Some(args.join(" ") + "\n"),
-        "pwd" => env::current_dir()
-            .ok()
-            .map(|p| p.display().to_string() + "\n"),
-        "exit" => std::process::exit(args.get(0).and_then(|s| s.parse().ok()).unwrap_or(0)),
-        "type" => Some(if let Some(arg) = args.get(0) {
-            if BUILTINS.contains(&arg.as_str()) {
-                format!("{} is a shell builtin\n", arg)
-            } else if let Some(path) = executables.get(arg) {
-                format!("{} is {}\n", arg, path)
+        "echo" => (Some(args.join(" ") + "\n"), 0),
+        "pwd" => match env::current_dir() {
+            Ok(p) => (Some(p.display().to_string() + "\n"), 0),
+            Err(_) => (None, 1),
+        },
+        "exit" => {
+            save_history(&history_file_path(), history);
+            std::process::exit(args.get(0).and_then(|s| s.parse().ok()).unwrap_or(0))
+        }
+        "type" => {
+            if let Some(arg) = args.get(0) {
+                if BUILTINS.contains(&arg.as_str()) {
+                    (Some(format!("{} is a shell builtin\n", arg)), 0)
+                } else if let Some(path) = executables.get(arg) {
+                    (Some(format!("{} is {}\n", arg, path)), 0)
+                } else {
+                    (Some(format!("{}: not found\n", arg)), 1)
+                }
             } else {
-                format!("{}: not found\n", arg)
+                (Some(format!("type: missing operand\n")), 1)
             }
-        } else {
-            format!("type: missing operand\n")
-        }),
+        }
         "cd" => {
-            if let Some(dir) = args.get(0) {
-                let path = shellexpand::tilde(dir).to_string();
-                if let Err(_e) = env::set_current_dir(&path) {
-                    // println!("{}", path);
-                    Some(format!("cd: {}: No such file or directory\n", dir))
-                } else {
-                    None
+            let dir = args.get(0).cloned().or_else(|| config.get("HOME"));
+            match dir {
+                Some(dir) => {
+                    let path = shellexpand::tilde(&dir).to_string();
+                    if let Err(_e) = env::set_current_dir(&path) {
+                        (Some(format!("cd: {}: No such file or directory\n", dir)), 1)
+                    } else {
+                        (None, 0)
+                    }
+                }
+                None => (None, 0),
+            }
+        }
+        "export" => {
+            for arg in &args {
+                if let Some((name, value)) = parse_assignment(arg) {
+                    env::set_var(&name, &value);
+                    config.set(&name, &value);
+                }
+            }
+            (None, 0)
+        }
+        "unset" => {
+            for name in &args {
+                env::remove_var(name);
+                config.unset(name);
+            }
+            (None, 0)
+        }
+        "history" => {
+            if args.get(0).map(|s| s.as_str()) == Some("-c") {
+                history.clear();
+                return (None, 0);
+            }
+            let count = args.get(0).and_then(|s| s.parse::<usize>().ok());
+            let start = match count {
+                Some(n) if n < history.len() => history.len() - n,
+                _ => 0,
+            };
+            let mut out = String::new();
+            for (idx, line) in history.iter().enumerate().skip(start) {
+                out.push_str(&format!("{:>4}  {}\n", idx + 1, line));
+            }
+            (Some(out), 0)
+        }
+        "alias" => {
+            if args.is_empty() {
+                let mut out = String::new();
+                for (name, value) in &config.aliases {
+                    out.push_str(&format!("alias {}='{}'\n", name, value));
                 }
+                (Some(out), 0)
             } else {
-                None
+                let mut out = String::new();
+                let mut code = 0;
+                for arg in &args {
+                    if let Some((name, value)) = parse_assignment(arg) {
+                        config.aliases.insert(name, value);
+                    } else if let Some(value) = config.aliases.get(arg) {
+                        out.push_str(&format!("alias {}='{}'\n", arg, value));
+                    } else {
+                        out.push_str(&format!("alias: {}: not found\n", arg));
+                        code = 1;
+                    }
+                }
+                (if out.is_empty() { None } else { Some(out) }, code)
+            }
+        }
+        "unalias" => {
+            for name in &args {
+                config.aliases.remove(name);
             }
+            (None, 0)
         }
-        // "history" => {
-        //     let mut out = String::new();
-        //     for (idx, line) in history.history().iter().enumerate() {
-        //         out.push_str(&format!("{:>4}  {}\n", idx + 1, line));
-        //     }
-        //     Some(out)
-        // }
+        _ => (None, 0),
+    }
+}
+
+/// Which descriptor a redirection applies to.
+#[derive(Clone, Copy, PartialEq)]
+enum Fd {
+    Stdout,
+    Stderr,
+}
+
+/// Whether a redirection truncates or appends to its target file.
+#[derive(Clone, Copy, PartialEq)]
+enum RedirectMode {
+    Truncate,
+    Append,
+}
+
+/// Recognizes a redirection operator token (`>`, `1>`, `>>`, `1>>`, `2>`,
+/// `2>>`), returning which descriptor and mode it selects.
+fn classify_redirect(token: &str) -> Option<(Fd, RedirectMode)> {
+    match token {
+        ">" | "1>" => Some((Fd::Stdout, RedirectMode::Truncate)),
+        ">>" | "1>>" => Some((Fd::Stdout, RedirectMode::Append)),
+        "2>" => Some((Fd::Stderr, RedirectMode::Truncate)),
+        "2>>" => Some((Fd::Stderr, RedirectMode::Append)),
         _ => None,
     }
 }
 
-fn parse_arguments(
-    command: String,
-) -> (
-    Vec<String>,
-    Vec<Vec<String>>,
-    Option<(String, String)>,
-    bool,
-) {
-    let mut cmd = Vec::new();
-    let mut args = Vec::new();
-    let mut redirection = None;
+fn open_redirect_file(mode: RedirectMode, filename: &str) -> io::Result<fs::File> {
+    match mode {
+        RedirectMode::Append => OpenOptions::new().append(true).create(true).open(filename),
+        RedirectMode::Truncate => OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(filename),
+    }
+}
+
+/// One pipeline's worth of commands, ready to execute: `cmds[i]`/`args[i]`
+/// is the i-th stage, piped into the next, with `redirections[i]` holding
+/// that stage's own `>`/`>>`/`2>`/`2>>` targets.
+struct ParsedCommand {
+    cmds: Vec<String>,
+    args: Vec<Vec<String>>,
+    redirections: Vec<Vec<(Fd, RedirectMode, String)>>,
+}
+
+/// How a pipeline is gated on the exit status of the one before it.
+#[derive(Clone, Copy, PartialEq)]
+enum Connector {
+    /// `;` or the first pipeline on the line - always runs.
+    Always,
+    /// `&&` - runs only if the previous pipeline succeeded.
+    And,
+    /// `||` - runs only if the previous pipeline failed.
+    Or,
+}
+
+/// Splits a flat token stream into pipeline segments at `;`, `&&`, and
+/// `||`, pairing each segment with the connector that gates it.
+fn split_into_segments(tokens: Vec<String>) -> Vec<(Vec<String>, Connector)> {
+    let mut segments = Vec::new();
+    let mut current = Vec::new();
+    let mut connector = Connector::Always;
 
-    let command_split: Vec<String> = shlex::split(&command).unwrap_or_default();
-    let has_pipe = command_split.contains(&"|".to_string());
+    for token in tokens {
+        match token.as_str() {
+            ";" | "&&" | "||" => {
+                segments.push((current, connector));
+                current = Vec::new();
+                connector = match token.as_str() {
+                    "&&" => Connector::And,
+                    "||" => Connector::Or,
+                    _ => Connector::Always,
+                };
+            }
+            _ => current.push(token),
+        }
+    }
+    segments.push((current, connector));
+
+    segments
+}
+
+/// Expands `tokens[0]` against `aliases`, re-splitting the alias value and
+/// repeating while the new first word is itself an alias. Aliases already
+/// expanded once in this chain are skipped to guard against recursion.
+fn expand_alias(mut tokens: Vec<String>, aliases: &BTreeMap<String, String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+
+    while let Some(value) = tokens.first().and_then(|first| aliases.get(first)) {
+        let first = tokens[0].clone();
+        if !seen.insert(first) {
+            break;
+        }
+        let mut expansion = shlex::split(value).unwrap_or_default();
+        expansion.extend(tokens.into_iter().skip(1));
+        tokens = expansion;
+    }
+
+    tokens
+}
+
+/// Splits one pipeline's tokens (already expanded) into commands, their
+/// arguments, and each stage's own redirections.
+fn parse_pipeline(tokens: Vec<String>, config: &Config) -> ParsedCommand {
+    let mut cmds = Vec::new();
+    let mut args = Vec::new();
+    let mut redirections = Vec::new();
 
     let mut command_parts = Vec::new();
     let mut current_part = Vec::new();
 
-    for part in command_split {
+    for part in tokens {
         if part == "|" {
             command_parts.push(current_part);
             current_part = Vec::new();
@@ -111,63 +406,91 @@ fn parse_arguments(
         command_parts.push(current_part);
     }
 
-    let redir_modes = ["1>", "2>", ">", ">>", "1>>", "2>>"];
-
     for part in command_parts {
+        let part = expand_alias(part, &config.aliases);
         let mut part_iter = VecDeque::from(part);
         if let Some(command_name) = part_iter.pop_front() {
-            cmd.push(command_name);
+            cmds.push(command_name);
         }
 
         let mut part_args = Vec::new();
+        let mut part_redirections = Vec::new();
         while let Some(arg) = part_iter.pop_front() {
-            if redir_modes.contains(&arg.as_str()) {
+            if let Some((fd, mode)) = classify_redirect(&arg) {
                 if let Some(fname) = part_iter.pop_front() {
-                    // filename = Some(fname);
-                    // redirect_mode = arg;
-                    redirection = Some((arg, fname));
+                    part_redirections.push((fd, mode, fname));
                 }
-                break;
             } else {
                 part_args.push(arg);
             }
         }
         args.push(part_args);
+        redirections.push(part_redirections);
     }
 
-    return (cmd, args, redirection, has_pipe);
+    ParsedCommand {
+        cmds,
+        args,
+        redirections,
+    }
 }
 
-fn parse_command(command: String) {
-    let (cmds, args, redirection, _has_pipe) = parse_arguments(command);
+/// Runs one pipeline's commands (already split on `|`) and returns its
+/// exit status.
+fn run_pipeline(parsed: ParsedCommand, history: &mut VecDeque<String>, config: &mut Config) -> i32 {
+    let ParsedCommand {
+        cmds,
+        args,
+        redirections,
+    } = parsed;
+
+    if cmds.is_empty() {
+        return config.status();
+    }
+    if cmds.len() == 1 && args[0].is_empty() {
+        if let Some((name, value)) = parse_assignment(&cmds[0]) {
+            config.set(&name, &value);
+            return 0;
+        }
+    }
 
     let mut processes: Vec<Child> = vec![];
     let mut prev_stdout = None;
+    let mut status = 0;
 
     for i in 0..cmds.len() {
         let cmd = cmds.get(i).unwrap().to_string();
         let cmd_args = args.get(i).unwrap();
+        let redirs = redirections.get(i).map(Vec::as_slice).unwrap_or(&[]);
+        // When a command repeats a redirection for the same fd, the last
+        // one wins, matching every other shell.
+        let stdout_redirect = redirs.iter().rev().find(|(fd, _, _)| *fd == Fd::Stdout);
+        let stderr_redirect = redirs.iter().rev().find(|(fd, _, _)| *fd == Fd::Stderr);
+        let is_last = i == cmds.len() - 1;
 
         let stdin = prev_stdout
             .take()
             .map(Stdio::from)
             .unwrap_or(Stdio::inherit());
-        let stdout = if i < cmds.len() - 1 {
+        let stdout = if let Some((_, mode, filename)) = stdout_redirect {
+            match open_redirect_file(*mode, filename) {
+                Ok(f) => Stdio::from(f),
+                Err(e) => {
+                    eprintln!("Failed to open file {}: {}", filename, e);
+                    return 1;
+                }
+            }
+        } else if !is_last {
             Stdio::piped()
-        } else if let Some((ref mode, ref filename)) = redirection {
-            let file = match mode.as_str() {
-                ">>" => OpenOptions::new().append(true).create(true).open(filename),
-                _ => OpenOptions::new()
-                    .write(true)
-                    .create(true)
-                    .truncate(true)
-                    .open(filename),
-            };
-            match file {
+        } else {
+            Stdio::inherit()
+        };
+        let stderr = if let Some((_, mode, filename)) = stderr_redirect {
+            match open_redirect_file(*mode, filename) {
                 Ok(f) => Stdio::from(f),
                 Err(e) => {
                     eprintln!("Failed to open file {}: {}", filename, e);
-                    return;
+                    return 1;
                 }
             }
         } else {
@@ -175,9 +498,19 @@ fn parse_command(command: String) {
         };
 
         if BUILTINS.contains(&cmd.as_str()) {
-            if let Some(output) = run_builtin(cmd, cmd_args.to_vec()) {
-                print!("{}", output);
+            let (output, code) = run_builtin(cmd, cmd_args.to_vec(), history, config);
+            if let Some(output) = output {
+                if let Some((_, mode, filename)) = stdout_redirect {
+                    let written = open_redirect_file(*mode, filename)
+                        .and_then(|mut f| f.write_all(output.as_bytes()));
+                    if let Err(e) = written {
+                        eprintln!("Failed to write to file {}: {}", filename, e);
+                    }
+                } else {
+                    print!("{}", output);
+                }
             }
+            status = code;
             continue;
         }
 
@@ -185,21 +518,31 @@ fn parse_command(command: String) {
             .args(cmd_args)
             .stdin(stdin)
             .stdout(stdout)
-            .stderr(Stdio::inherit())
+            .stderr(stderr)
             .spawn()
         {
             Ok(mut child) => {
                 prev_stdout = child.stdout.take();
-                processes.push(child);
+                if is_last {
+                    status = match child.wait() {
+                        Ok(exit_status) => exit_status.code().unwrap_or(1),
+                        Err(e) => {
+                            eprintln!("Process error: {}", e);
+                            1
+                        }
+                    };
+                } else {
+                    processes.push(child);
+                }
             }
             Err(_e) => {
                 eprintln!("{}: command not found", cmd);
-                return;
+                return 127;
             }
         }
     }
 
-    // Wait for all child processes
+    // Wait for the remaining (non-last) pipeline stages.
     for mut child in processes {
         if let Err(e) = child.wait() {
             eprintln!("Process error: {}", e);
@@ -208,22 +551,201 @@ fn parse_command(command: String) {
 
     // Ensure stdout is flushed
     io::stdout().flush().unwrap();
+
+    status
+}
+
+/// Splits `command` on `;`/`&&`/`||` and runs each pipeline in order,
+/// short-circuiting `&&`/`||` segments based on the previous exit status.
+/// Returns the last pipeline's exit status.
+fn parse_command(command: String, history: &mut VecDeque<String>, config: &mut Config) -> i32 {
+    let tokens = shlex::split(&command).unwrap_or_default();
+    let mut status = config.status();
+
+    for (raw_tokens, connector) in split_into_segments(tokens) {
+        if raw_tokens.is_empty() {
+            continue;
+        }
+
+        let should_run = match connector {
+            Connector::Always => true,
+            Connector::And => status == 0,
+            Connector::Or => status != 0,
+        };
+        if !should_run {
+            continue;
+        }
+
+        let tokens = raw_tokens
+            .into_iter()
+            .map(|token| expand_variables(&token, config))
+            .collect();
+        status = run_pipeline(parse_pipeline(tokens, config), history, config);
+        config.set_status(status);
+    }
+
+    status
+}
+
+/// Puts `fd` into raw mode, returning the prior settings to restore later.
+/// Returns `None` (instead of panicking) when `fd` isn't a real TTY, e.g.
+/// a piped or redirected stdin.
+fn enable_raw_mode(fd: i32) -> Option<Termios> {
+    let original = Termios::from_fd(fd).ok()?;
+    let mut raw = original;
+    raw.c_lflag &= !(ICANON | ECHO);
+    raw.c_cc[VMIN] = 1;
+    raw.c_cc[VTIME] = 0;
+    tcsetattr(fd, TCSANOW, &raw).ok()?;
+    Some(original)
+}
+
+fn disable_raw_mode(fd: i32, original: Option<Termios>) {
+    if let Some(original) = original {
+        let _ = tcsetattr(fd, TCSANOW, &original);
+    }
+}
+
+fn redraw_line(stdout: &mut impl Write, line: &str) {
+    print!("\r$ \x1b[K{}", line);
+    stdout.flush().unwrap();
+}
+
+/// Reads one line of input with TAB completion, backspace, up/down history
+/// recall, and raw echo.
+fn read_line(
+    bytes: &mut impl Iterator<Item = io::Result<u8>>,
+    stdout: &mut impl Write,
+    history: &VecDeque<String>,
+) -> Option<String> {
+    let mut line = String::new();
+    let mut history_pos = history.len();
+
+    loop {
+        let byte = match bytes.next() {
+            Some(Ok(b)) => b,
+            _ => return None,
+        };
+
+        match byte {
+            b'\n' | b'\r' => {
+                print!("\r\n");
+                stdout.flush().unwrap();
+                return Some(line);
+            }
+            0x7f | 0x08 => {
+                if line.pop().is_some() {
+                    print!("\u{8} \u{8}");
+                    stdout.flush().unwrap();
+                }
+            }
+            b'\t' => {
+                let executables = load_executables();
+                let (candidates, prefix) = completer::complete(&line, &executables);
+                match candidates.as_slice() {
+                    [] => {}
+                    [only] => {
+                        let suffix = &only[prefix.len()..];
+                        line.push_str(suffix);
+                        print!("{}", suffix);
+                        if !suffix.ends_with('/') {
+                            line.push(' ');
+                            print!(" ");
+                        }
+                        stdout.flush().unwrap();
+                    }
+                    many => {
+                        let common = completer::common_prefix(many);
+                        if common.len() > prefix.len() {
+                            let suffix = &common[prefix.len()..];
+                            line.push_str(suffix);
+                            print!("{}", suffix);
+                        } else {
+                            print!("\r\n{}\r\n$ {}", many.join("  "), line);
+                        }
+                        stdout.flush().unwrap();
+                    }
+                }
+            }
+            0x1b => {
+                let (first, second) = (bytes.next(), bytes.next());
+                if let (Some(Ok(b'[')), Some(Ok(code))) = (first, second) {
+                    match code {
+                        b'A' if history_pos > 0 => {
+                            history_pos -= 1;
+                            line = history.get(history_pos).cloned().unwrap_or_default();
+                            redraw_line(stdout, &line);
+                        }
+                        b'B' if history_pos < history.len() => {
+                            history_pos += 1;
+                            line = history.get(history_pos).cloned().unwrap_or_default();
+                            redraw_line(stdout, &line);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            b => {
+                let ch = b as char;
+                line.push(ch);
+                print!("{}", ch);
+                stdout.flush().unwrap();
+            }
+        }
+    }
+}
+
+/// Runs `command` through history + parsing, recording it unless blank.
+fn run_line(command: String, history: &mut VecDeque<String>, config: &mut Config) {
+    if !command.trim().is_empty() {
+        history.push_back(command.trim().to_string());
+    }
+    let _ = parse_command(command, history, config);
 }
 
 fn repl() {
     let mut stdout = io::stdout();
     let stdin = io::stdin();
+    let original_termios = enable_raw_mode(0);
+    let history_path = history_file_path();
+    let mut history = load_history(&history_path);
+    let mut config = Config::new();
+
+    if original_termios.is_none() {
+        // stdin isn't a TTY (piped/redirected input) - fall back to plain
+        // line reading instead of the raw-mode editor.
+        for line in stdin.lock().lines() {
+            print!("$ ");
+            stdout.flush().unwrap();
+
+            let command = match line {
+                Ok(command) => command,
+                Err(_) => break,
+            };
+
+            run_line(command, &mut history, &mut config);
+        }
+
+        save_history(&history_path, &history);
+        return;
+    }
+
+    let mut bytes = stdin.lock().bytes();
 
     loop {
         print!("$ ");
         stdout.flush().unwrap();
 
-        // Wait for user input
-        let mut command = String::new();
-        stdin.read_line(&mut command).unwrap();
+        let command = match read_line(&mut bytes, &mut stdout, &history) {
+            Some(command) => command,
+            None => break,
+        };
 
-        parse_command(command);
+        run_line(command, &mut history, &mut config);
     }
+
+    save_history(&history_path, &history);
+    disable_raw_mode(0, original_termios);
 }
 
 fn main() {